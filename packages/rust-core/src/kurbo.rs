@@ -1,9 +1,886 @@
+use kurbo::{BezPath, CubicBez, ParamCurve, ParamCurveArclen, PathEl, PathSeg, Point, QuadBez};
 use svgtypes::{PathParser, PathSegment};
-use kurbo::{CubicBez, QuadBez};
+
+/// Parses an SVG path `d` string into a [`kurbo::BezPath`].
+///
+/// Tracks the current point and, for the `S`/`T` smooth variants, the
+/// previous cubic/quadratic control point so it can be reflected through
+/// the current point per the SVG spec. Relative commands accumulate onto
+/// the running position. Elliptical arcs are lowered to cubic Béziers via
+/// [`arc_to_cubics`].
+fn svg_to_bezpath(path: &str) -> BezPath {
+    let mut bez = BezPath::new();
+    let mut current = Point::ORIGIN;
+    let mut subpath_start = Point::ORIGIN;
+    // Previous cubic/quadratic control point, for reflecting S/T commands.
+    let mut prev_cubic_ctrl: Option<Point> = None;
+    let mut prev_quad_ctrl: Option<Point> = None;
+
+    for segment in PathParser::from(path) {
+        let segment = segment.unwrap();
+        let mut had_cubic_ctrl = false;
+        let mut had_quad_ctrl = false;
+
+        match segment {
+            PathSegment::MoveTo { abs, x, y } => {
+                let p = resolve(current, abs, x, y);
+                bez.move_to(p);
+                current = p;
+                subpath_start = p;
+            }
+            PathSegment::LineTo { abs, x, y } => {
+                let p = resolve(current, abs, x, y);
+                bez.line_to(p);
+                current = p;
+            }
+            PathSegment::HorizontalLineTo { abs, x } => {
+                let p = Point::new(if abs { x } else { current.x + x }, current.y);
+                bez.line_to(p);
+                current = p;
+            }
+            PathSegment::VerticalLineTo { abs, y } => {
+                let p = Point::new(current.x, if abs { y } else { current.y + y });
+                bez.line_to(p);
+                current = p;
+            }
+            PathSegment::CurveTo {
+                abs,
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let c1 = resolve(current, abs, x1, y1);
+                let c2 = resolve(current, abs, x2, y2);
+                let p = resolve(current, abs, x, y);
+                bez.curve_to(c1, c2, p);
+                prev_cubic_ctrl = Some(c2);
+                had_cubic_ctrl = true;
+                current = p;
+            }
+            PathSegment::SmoothCurveTo { abs, x2, y2, x, y } => {
+                let c1 = prev_cubic_ctrl
+                    .map(|ctrl| reflect(ctrl, current))
+                    .unwrap_or(current);
+                let c2 = resolve(current, abs, x2, y2);
+                let p = resolve(current, abs, x, y);
+                bez.curve_to(c1, c2, p);
+                prev_cubic_ctrl = Some(c2);
+                had_cubic_ctrl = true;
+                current = p;
+            }
+            PathSegment::Quadratic { abs, x1, y1, x, y } => {
+                let c1 = resolve(current, abs, x1, y1);
+                let p = resolve(current, abs, x, y);
+                bez.quad_to(c1, p);
+                prev_quad_ctrl = Some(c1);
+                had_quad_ctrl = true;
+                current = p;
+            }
+            PathSegment::SmoothQuadratic { abs, x, y } => {
+                let c1 = prev_quad_ctrl
+                    .map(|ctrl| reflect(ctrl, current))
+                    .unwrap_or(current);
+                let p = resolve(current, abs, x, y);
+                bez.quad_to(c1, p);
+                prev_quad_ctrl = Some(c1);
+                had_quad_ctrl = true;
+                current = p;
+            }
+            PathSegment::EllipticalArc {
+                abs,
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let end = resolve(current, abs, x, y);
+                if (end - current).hypot() < 1e-9 {
+                    // Zero-length arcs are dropped per spec.
+                } else if rx.abs() < 1e-9 || ry.abs() < 1e-9 {
+                    bez.line_to(end);
+                } else {
+                    for (c1, c2, p) in
+                        arc_to_cubics(current, end, rx, ry, x_axis_rotation, large_arc, sweep)
+                    {
+                        bez.curve_to(c1, c2, p);
+                    }
+                }
+                current = end;
+            }
+            PathSegment::ClosePath { .. } => {
+                bez.close_path();
+                current = subpath_start;
+            }
+        }
+
+        if !had_cubic_ctrl {
+            prev_cubic_ctrl = None;
+        }
+        if !had_quad_ctrl {
+            prev_quad_ctrl = None;
+        }
+    }
+
+    bez
+}
+
+fn resolve(current: Point, abs: bool, x: f64, y: f64) -> Point {
+    if abs {
+        Point::new(x, y)
+    } else {
+        Point::new(current.x + x, current.y + y)
+    }
+}
+
+/// Reflects `ctrl` through `center`, used to derive the implicit control
+/// point of `S`/`T` smooth curve commands.
+fn reflect(ctrl: Point, center: Point) -> Point {
+    Point::new(2.0 * center.x - ctrl.x, 2.0 * center.y - ctrl.y)
+}
+
+/// Decomposes an SVG elliptical arc into cubic Bézier segments.
+///
+/// Follows the endpoint-to-center parameterization from the SVG
+/// implementation notes, then splits the angular sweep into pieces of at
+/// most 90° so each cubic's control points (offset by `k = (4/3)tan(θ/4)`
+/// along the tangent directions) stay a good approximation of the ellipse.
+fn arc_to_cubics(
+    start: Point,
+    end: Point,
+    mut rx: f64,
+    mut ry: f64,
+    x_axis_rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+) -> Vec<(Point, Point, Point)> {
+    rx = rx.abs();
+    ry = ry.abs();
+    let phi = x_axis_rotation.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    // Step 1: compute (x1', y1'), the start point in the rotated ellipse frame.
+    let dx2 = (start.x - end.x) / 2.0;
+    let dy2 = (start.y - end.y) / 2.0;
+    let x1p = cos_phi * dx2 + sin_phi * dy2;
+    let y1p = -sin_phi * dx2 + cos_phi * dy2;
+
+    // Correct out-of-range radii.
+    let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+    if lambda > 1.0 {
+        let scale = lambda.sqrt();
+        rx *= scale;
+        ry *= scale;
+    }
+
+    // Step 2: compute the center in the rotated frame, then transform back.
+    let rx2 = rx * rx;
+    let ry2 = ry * ry;
+    let x1p2 = x1p * x1p;
+    let y1p2 = y1p * y1p;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let num = rx2 * ry2 - rx2 * y1p2 - ry2 * x1p2;
+    let denom = rx2 * y1p2 + ry2 * x1p2;
+    let coef = sign * (num.max(0.0) / denom).sqrt();
+    let cxp = coef * (rx * y1p / ry);
+    let cyp = coef * -(ry * x1p / rx);
+
+    let cx = cos_phi * cxp - sin_phi * cyp + (start.x + end.x) / 2.0;
+    let cy = sin_phi * cxp + cos_phi * cyp + (start.y + end.y) / 2.0;
+
+    // Step 3: compute the start angle and the angular sweep.
+    let theta1 = angle_between(1.0, 0.0, (x1p - cxp) / rx, (y1p - cyp) / ry);
+    let mut delta_theta = angle_between(
+        (x1p - cxp) / rx,
+        (y1p - cyp) / ry,
+        (-x1p - cxp) / rx,
+        (-y1p - cyp) / ry,
+    );
+    if !sweep && delta_theta > 0.0 {
+        delta_theta -= std::f64::consts::TAU;
+    } else if sweep && delta_theta < 0.0 {
+        delta_theta += std::f64::consts::TAU;
+    }
+
+    // Step 4: split into segments of at most 90 degrees.
+    let segment_count = (delta_theta.abs() / (std::f64::consts::FRAC_PI_2)).ceil().max(1.0) as usize;
+    let segment_sweep = delta_theta / segment_count as f64;
+    let k = 4.0 / 3.0 * (segment_sweep / 4.0).tan();
+
+    let ellipse_point = |theta: f64| -> Point {
+        let (sin_t, cos_t) = theta.sin_cos();
+        Point::new(
+            cx + rx * cos_t * cos_phi - ry * sin_t * sin_phi,
+            cy + rx * cos_t * sin_phi + ry * sin_t * cos_phi,
+        )
+    };
+    let ellipse_tangent = |theta: f64| -> (f64, f64) {
+        let (sin_t, cos_t) = theta.sin_cos();
+        (
+            -rx * sin_t * cos_phi - ry * cos_t * sin_phi,
+            -rx * sin_t * sin_phi + ry * cos_t * cos_phi,
+        )
+    };
+
+    let mut cubics = Vec::with_capacity(segment_count);
+    let mut theta = theta1;
+    let mut from = start;
+    for i in 0..segment_count {
+        let next_theta = if i == segment_count - 1 {
+            theta1 + delta_theta
+        } else {
+            theta + segment_sweep
+        };
+        let to = if i == segment_count - 1 {
+            end
+        } else {
+            ellipse_point(next_theta)
+        };
+        let (t1x, t1y) = ellipse_tangent(theta);
+        let (t2x, t2y) = ellipse_tangent(next_theta);
+        let c1 = Point::new(from.x + k * t1x, from.y + k * t1y);
+        let c2 = Point::new(to.x - k * t2x, to.y - k * t2y);
+        cubics.push((c1, c2, to));
+        theta = next_theta;
+        from = to;
+    }
+    cubics
+}
+
+/// Angle between vectors `(ux, uy)` and `(vx, vy)`, signed by their cross product.
+fn angle_between(ux: f64, uy: f64, vx: f64, vy: f64) -> f64 {
+    let dot = ux * vx + uy * vy;
+    let len = (ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt();
+    let mut angle = (dot / len).clamp(-1.0, 1.0).acos();
+    if ux * vy - uy * vx < 0.0 {
+        angle = -angle;
+    }
+    angle
+}
+
+/// Evenly distributes `count` points along `path` by arc length, so marchers
+/// dropped onto a parsed drill path are spaced equidistantly regardless of
+/// how its Béziers are distributed.
+///
+/// `path.segments()` already turns a subpath's trailing `ClosePath` into a
+/// line back to its start, so closed loops wrap the final distance back to
+/// the start for free.
+fn sample_along_path(path: &BezPath, count: usize) -> Vec<Point> {
+    const ACCURACY: f64 = 1e-6;
+
+    let segments: Vec<PathSeg> = path.segments().collect();
+    if count == 0 || segments.is_empty() {
+        return Vec::new();
+    }
+    if count == 1 {
+        return vec![segments[0].eval(0.0)];
+    }
+
+    let seg_lengths: Vec<f64> = segments.iter().map(|seg| seg.arclen(ACCURACY)).collect();
+    let total: f64 = seg_lengths.iter().sum();
+
+    let mut points = Vec::with_capacity(count);
+    for i in 0..count {
+        let target = total * i as f64 / (count - 1) as f64;
+        let mut remaining = target;
+        let mut chosen = segments.len() - 1;
+        for (idx, &len) in seg_lengths.iter().enumerate() {
+            if idx == segments.len() - 1 || remaining <= len {
+                chosen = idx;
+                break;
+            }
+            remaining -= len;
+        }
+        let seg = segments[chosen];
+        let t = seg.inv_arclen(remaining.min(seg_lengths[chosen]), ACCURACY);
+        points.push(seg.eval(t));
+    }
+    points
+}
+
+/// Flattens `path` into polylines, one point vector per subpath, for
+/// rendering grids and collision previews that can't consume Béziers
+/// directly.
+///
+/// Each quad/cubic is recursively subdivided with de Casteljau's algorithm
+/// (`t = 0.5`) until its control points fall within `tolerance` of the
+/// chord between its endpoints, with a depth cap guarding against
+/// infinite recursion on degenerate curves. `tolerance` around `0.05`
+/// units is a good accuracy/size tradeoff.
+///
+/// A huge or cusp-like control polygon can need far more than `MAX_DEPTH`
+/// subdivisions to satisfy an absolute `tolerance`, so each segment also
+/// carries a [`MAX_POINTS_PER_SEGMENT`] budget: once exhausted, subdivision
+/// stops and the remaining curve is truncated to its current approximation
+/// rather than recursing to the depth cap (2^24 points for one segment).
+/// This keeps a single oddly-scaled imported path from hanging callers that
+/// run on every render tick, at the cost of a coarser approximation for
+/// that one segment.
+fn flatten_path(path: &BezPath, tolerance: f64) -> Vec<Vec<Point>> {
+    const MAX_DEPTH: u32 = 24;
+
+    let mut subpaths = Vec::new();
+    let mut current_subpath: Vec<Point> = Vec::new();
+    let mut current = Point::ORIGIN;
+    let mut subpath_start = Point::ORIGIN;
+
+    for el in path.elements() {
+        match *el {
+            PathEl::MoveTo(p) => {
+                if current_subpath.len() > 1 {
+                    subpaths.push(std::mem::take(&mut current_subpath));
+                } else {
+                    current_subpath.clear();
+                }
+                current_subpath.push(p);
+                current = p;
+                subpath_start = p;
+            }
+            PathEl::LineTo(p) => {
+                current_subpath.push(p);
+                current = p;
+            }
+            PathEl::QuadTo(c1, p) => {
+                let mut budget = MAX_POINTS_PER_SEGMENT - 1;
+                flatten_quad(
+                    QuadBez::new(current, c1, p),
+                    tolerance,
+                    0,
+                    MAX_DEPTH,
+                    &mut budget,
+                    &mut current_subpath,
+                );
+                current = p;
+            }
+            PathEl::CurveTo(c1, c2, p) => {
+                let mut budget = MAX_POINTS_PER_SEGMENT - 1;
+                flatten_cubic(
+                    CubicBez::new(current, c1, c2, p),
+                    tolerance,
+                    0,
+                    MAX_DEPTH,
+                    &mut budget,
+                    &mut current_subpath,
+                );
+                current = p;
+            }
+            PathEl::ClosePath => {
+                current_subpath.push(subpath_start);
+                current = subpath_start;
+            }
+        }
+    }
+    if current_subpath.len() > 1 {
+        subpaths.push(current_subpath);
+    }
+    subpaths
+}
+
+/// Hard cap on how many points a single quad/cubic segment may expand into
+/// during adaptive flattening, independent of the depth cap. Bounds the
+/// worst case for pathological control polygons (e.g. a huge or cusp-like
+/// curve that never satisfies an absolute `tolerance`) that would otherwise
+/// recurse all the way to `MAX_DEPTH` and emit millions of points for one
+/// segment. An unsplit segment emits one point and each subdivision adds
+/// one more, so the subdivision budget is one less than this cap.
+const MAX_POINTS_PER_SEGMENT: usize = 2048;
+
+fn flatten_cubic(
+    c: CubicBez,
+    tolerance: f64,
+    depth: u32,
+    max_depth: u32,
+    budget: &mut usize,
+    out: &mut Vec<Point>,
+) {
+    let flat = perp_distance(c.p1, c.p0, c.p3) <= tolerance
+        && perp_distance(c.p2, c.p0, c.p3) <= tolerance;
+    if depth >= max_depth || flat || *budget == 0 {
+        out.push(c.p3);
+        return;
+    }
+    *budget -= 1;
+    let (a, b) = c.subdivide();
+    flatten_cubic(a, tolerance, depth + 1, max_depth, budget, out);
+    flatten_cubic(b, tolerance, depth + 1, max_depth, budget, out);
+}
+
+fn flatten_quad(
+    q: QuadBez,
+    tolerance: f64,
+    depth: u32,
+    max_depth: u32,
+    budget: &mut usize,
+    out: &mut Vec<Point>,
+) {
+    let flat = perp_distance(q.p1, q.p0, q.p2) <= tolerance;
+    if depth >= max_depth || flat || *budget == 0 {
+        out.push(q.p2);
+        return;
+    }
+    *budget -= 1;
+    let (a, b) = q.subdivide();
+    flatten_quad(a, tolerance, depth + 1, max_depth, budget, out);
+    flatten_quad(b, tolerance, depth + 1, max_depth, budget, out);
+}
+
+/// Perpendicular distance from `p` to the line through `a` and `b`, used as
+/// the flatness test during adaptive subdivision.
+fn perp_distance(p: Point, a: Point, b: Point) -> f64 {
+    let d = b - a;
+    let len = d.hypot();
+    if len < 1e-9 {
+        return (p - a).hypot();
+    }
+    ((p.x - a.x) * d.y - (p.y - a.y) * d.x).abs() / len
+}
+
+/// Which winding rule [`contains`] uses to decide interior vs. exterior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+/// Tests whether `p` falls inside `path`, so OpenMarch can select all
+/// marchers enclosed by an imported SVG region.
+///
+/// Flattens each closed subpath to line segments and casts a ray in the
+/// `+x` direction from `p`, accumulating the signed winding number from
+/// the segments it crosses. `NonZero` treats the point as inside when the
+/// winding number is nonzero; `EvenOdd` treats it as inside when the
+/// crossing count is odd.
+fn contains(path: &BezPath, p: Point, rule: FillRule) -> bool {
+    const TOLERANCE: f64 = 0.05;
+
+    let mut winding = 0i32;
+    for mut subpath in flatten_path(path, TOLERANCE) {
+        if subpath.len() < 2 {
+            continue;
+        }
+        if subpath.first() != subpath.last() {
+            subpath.push(subpath[0]);
+        }
+        for edge in subpath.windows(2) {
+            winding += edge_winding(edge[0], edge[1], p);
+        }
+    }
+
+    match rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Signed contribution of edge `a -> b` to the winding number around `p`
+/// for a ray cast in `+x`. Uses the half-open convention (an endpoint
+/// exactly on the ray counts as at-or-below it) so shared vertices between
+/// edges are never double-counted.
+fn edge_winding(a: Point, b: Point, p: Point) -> i32 {
+    let above_a = a.y > p.y;
+    let above_b = b.y > p.y;
+    if above_a == above_b {
+        return 0;
+    }
+    let t = (p.y - a.y) / (b.y - a.y);
+    let x_intersect = a.x + t * (b.x - a.x);
+    if x_intersect <= p.x {
+        return 0;
+    }
+    if above_b {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Serializes `path` back to an SVG `d` string, with coordinates formatted
+/// to `precision` decimal places and trailing zeros trimmed.
+///
+/// Paired with [`svg_to_bezpath`], parse → edit → serialize round-trips
+/// element for element for everything except arcs, which are already
+/// lowered to cubics by the parser; coordinates survive only up to the
+/// `precision` rounding.
+fn bezpath_to_svg(path: &BezPath, precision: usize) -> String {
+    let mut out = String::new();
+    for el in path.elements() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        match *el {
+            PathEl::MoveTo(p) => {
+                out.push('M');
+                out.push_str(&format_point(p, precision));
+            }
+            PathEl::LineTo(p) => {
+                out.push('L');
+                out.push_str(&format_point(p, precision));
+            }
+            PathEl::QuadTo(c1, p) => {
+                out.push('Q');
+                out.push_str(&format_point(c1, precision));
+                out.push(' ');
+                out.push_str(&format_point(p, precision));
+            }
+            PathEl::CurveTo(c1, c2, p) => {
+                out.push('C');
+                out.push_str(&format_point(c1, precision));
+                out.push(' ');
+                out.push_str(&format_point(c2, precision));
+                out.push(' ');
+                out.push_str(&format_point(p, precision));
+            }
+            PathEl::ClosePath => out.push('Z'),
+        }
+    }
+    out
+}
+
+fn format_point(p: Point, precision: usize) -> String {
+    format!("{},{}", format_num(p.x, precision), format_num(p.y, precision))
+}
+
+/// Formats `v` to `precision` decimal places, trimming trailing zeros and
+/// a now-redundant trailing decimal point. A negative value that rounds to
+/// zero at this precision (e.g. `-0.0001` at 2 places) drops its sign
+/// rather than emitting a stray `-0`.
+fn format_num(v: f64, precision: usize) -> String {
+    let s = format!("{v:.precision$}");
+    let trimmed = if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    };
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed
+    }
+}
+
 fn main() {
-    let mut segments = Vec::new();
-    for segment in PathParser::from("M118.02,51.5800 Q77.49,33.26 200.8,458.98 Q400.23,382.58 110.96,268.34 Q413.44,130.86 471.48,12.82 C400.22,96.72 154.92,313.49 365.95,427.32 C440.03,43.36 302.93,335.85 252.98,88.9 Q44.67,467.29 432.74,273.82 Q150.12,454.44 286.18,441.16 C206.97,299.46 215.52,80.66 152.56,406.3 Z") {
-        segments.push(segment.unwrap());
+    let path = svg_to_bezpath("M118.02,51.5800 Q77.49,33.26 200.8,458.98 Q400.23,382.58 110.96,268.34 Q413.44,130.86 471.48,12.82 C400.22,96.72 154.92,313.49 365.95,427.32 C440.03,43.36 302.93,335.85 252.98,88.9 Q44.67,467.29 432.74,273.82 Q150.12,454.44 286.18,441.16 C206.97,299.46 215.52,80.66 152.56,406.3 Z");
+    println!("{} path elements", path.elements().len());
+    let marchers = sample_along_path(&path, 12);
+    println!("{} evenly spaced marcher positions", marchers.len());
+    let polylines = flatten_path(&path, 0.05);
+    println!("{} flattened subpaths", polylines.len());
+    let center = Point::new(250.0, 250.0);
+    let inside_non_zero = contains(&path, center, FillRule::NonZero);
+    let inside_even_odd = contains(&path, center, FillRule::EvenOdd);
+    println!("center point inside formation: NonZero={inside_non_zero} EvenOdd={inside_even_odd}");
+    let d = bezpath_to_svg(&path, 2);
+    println!("{d}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_points_close(a: Point, b: Point) {
+        assert!(
+            (a - b).hypot() < 1e-6,
+            "expected {a:?} to be close to {b:?}"
+        );
+    }
+
+    fn rotate_point(p: Point, degrees: f64) -> Point {
+        let r = degrees.to_radians();
+        let (sin_r, cos_r) = r.sin_cos();
+        Point::new(p.x * cos_r - p.y * sin_r, p.x * sin_r + p.y * cos_r)
+    }
+
+    #[test]
+    fn arc_semicircle_passes_through_expected_midpoint() {
+        // A unit-radius arc spanning a diameter has no freedom in its
+        // center, so this exercises the center/theta formulas without
+        // any large-arc/sweep ambiguity.
+        let cubics = arc_to_cubics(Point::new(-1.0, 0.0), Point::new(1.0, 0.0), 1.0, 1.0, 0.0, false, true);
+        assert_points_close(cubics.last().unwrap().2, Point::new(1.0, 0.0));
+
+        let mut bez = BezPath::new();
+        bez.move_to(Point::new(-1.0, 0.0));
+        for (c1, c2, p) in &cubics {
+            bez.curve_to(*c1, *c2, *p);
+        }
+        let polyline = &flatten_path(&bez, 1e-5)[0];
+        let min_y = polyline.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        assert!((min_y + 1.0).abs() < 1e-3, "expected arc to dip to y=-1, got {min_y}");
+    }
+
+    #[test]
+    fn arc_large_arc_flag_picks_the_reflex_sweep() {
+        // Same start/end/radii/sweep, differing only in `large_arc`, so any
+        // regression in the `sign`/`delta_theta` branch shows up as the two
+        // producing the same (wrong) number of 90-degree segments.
+        let minor = arc_to_cubics(Point::new(0.0, 0.0), Point::new(10.0, 0.0), 10.0, 10.0, 0.0, false, true);
+        let major = arc_to_cubics(Point::new(0.0, 0.0), Point::new(10.0, 0.0), 10.0, 10.0, 0.0, true, true);
+        assert_eq!(minor.len(), 1);
+        assert_eq!(major.len(), 4);
+        assert_points_close(minor.last().unwrap().2, Point::new(10.0, 0.0));
+        assert_points_close(major.last().unwrap().2, Point::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn arc_rotation_matches_transformed_unrotated_arc() {
+        // Rotating an arc's endpoints and its x-axis-rotation parameter by
+        // the same angle should just rotate the resulting cubics, proving
+        // the `phi`/`sin_phi`/`cos_phi` handling is consistent.
+        let start = Point::new(-2.0, 0.0);
+        let end = Point::new(1.0, 1.5);
+        let rotation_deg = 40.0;
+
+        let base = arc_to_cubics(start, end, 3.0, 1.5, 0.0, false, true);
+        let rotated = arc_to_cubics(
+            rotate_point(start, rotation_deg),
+            rotate_point(end, rotation_deg),
+            3.0,
+            1.5,
+            rotation_deg,
+            false,
+            true,
+        );
+
+        assert_eq!(base.len(), rotated.len());
+        for ((c1, c2, p), (rc1, rc2, rp)) in base.iter().zip(rotated.iter()) {
+            assert_points_close(rotate_point(*c1, rotation_deg), *rc1);
+            assert_points_close(rotate_point(*c2, rotation_deg), *rc2);
+            assert_points_close(rotate_point(*p, rotation_deg), *rp);
+        }
+    }
+
+    #[test]
+    fn zero_length_arc_emits_nothing() {
+        let path = svg_to_bezpath("M5,5 A3,3 0 0 1 5,5 L10,10");
+        let els: Vec<PathEl> = path.elements().to_vec();
+        assert_eq!(
+            els,
+            vec![
+                PathEl::MoveTo(Point::new(5.0, 5.0)),
+                PathEl::LineTo(Point::new(10.0, 10.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn sample_along_path_spaces_points_evenly_on_a_closed_square() {
+        // A 4x4 closed square has a 16-unit perimeter; 5 samples at
+        // quarter-perimeter spacing should land exactly on its corners,
+        // including wrapping the last sample back to the start.
+        let mut square = BezPath::new();
+        square.move_to(Point::new(0.0, 0.0));
+        square.line_to(Point::new(4.0, 0.0));
+        square.line_to(Point::new(4.0, 4.0));
+        square.line_to(Point::new(0.0, 4.0));
+        square.close_path();
+
+        let points = sample_along_path(&square, 5);
+        assert_eq!(points.len(), 5);
+        assert_points_close(points[0], Point::new(0.0, 0.0));
+        assert_points_close(points[1], Point::new(4.0, 0.0));
+        assert_points_close(points[2], Point::new(4.0, 4.0));
+        assert_points_close(points[3], Point::new(0.0, 4.0));
+        assert_points_close(points[4], Point::new(0.0, 0.0));
     }
 
+    #[test]
+    fn sample_along_path_handles_small_counts() {
+        let mut line = BezPath::new();
+        line.move_to(Point::new(0.0, 0.0));
+        line.line_to(Point::new(10.0, 0.0));
+
+        assert_eq!(sample_along_path(&line, 0), Vec::<Point>::new());
+        assert_eq!(sample_along_path(&line, 1), vec![Point::new(0.0, 0.0)]);
+    }
+
+    #[test]
+    fn flatten_path_stays_within_tolerance_of_the_true_curve() {
+        let curve = CubicBez::new(
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 100.0),
+            Point::new(100.0, 100.0),
+            Point::new(100.0, 0.0),
+        );
+        let mut bez = BezPath::new();
+        bez.move_to(curve.p0);
+        bez.curve_to(curve.p1, curve.p2, curve.p3);
+
+        let tolerance = 0.05;
+        let polyline = &flatten_path(&bez, tolerance)[0];
+        assert!(
+            polyline.len() > 2,
+            "a curved segment should be subdivided into more than its endpoints"
+        );
+
+        // Densely sample the true curve and check every sample stays close
+        // to the flattened polyline, bounding the approximation error a
+        // caller relies on `tolerance` to control.
+        let mut max_deviation = 0.0_f64;
+        for i in 0..=1000 {
+            let t = i as f64 / 1000.0;
+            let p = curve.eval(t);
+            let nearest = polyline
+                .windows(2)
+                .map(|w| perp_distance(p, w[0], w[1]))
+                .fold(f64::INFINITY, f64::min);
+            max_deviation = max_deviation.max(nearest);
+        }
+        assert!(
+            max_deviation < tolerance * 10.0,
+            "flattened polyline deviated from the curve by {max_deviation}, tolerance was {tolerance}"
+        );
+    }
+
+    #[test]
+    fn flatten_path_splits_on_each_subpath() {
+        let mut bez = BezPath::new();
+        bez.move_to(Point::new(0.0, 0.0));
+        bez.line_to(Point::new(1.0, 0.0));
+        bez.move_to(Point::new(5.0, 5.0));
+        bez.line_to(Point::new(6.0, 5.0));
+
+        let subpaths = flatten_path(&bez, 0.05);
+        assert_eq!(subpaths.len(), 2);
+        assert_eq!(subpaths[0], vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)]);
+        assert_eq!(subpaths[1], vec![Point::new(5.0, 5.0), Point::new(6.0, 5.0)]);
+    }
+
+    #[test]
+    fn flatten_path_bounds_points_on_a_pathological_huge_curve() {
+        // A curve whose control points dwarf the tolerance can need far
+        // more than MAX_DEPTH subdivisions to satisfy an absolute flatness
+        // test; MAX_POINTS_PER_SEGMENT must cut this off well short of
+        // the 2^24-point worst case.
+        let mut bez = BezPath::new();
+        bez.move_to(Point::new(0.0, 0.0));
+        bez.curve_to(
+            Point::new(1e15, 1e15),
+            Point::new(-1e15, 1e15),
+            Point::new(0.0, 0.0),
+        );
+
+        let polyline = &flatten_path(&bez, 0.05)[0];
+        assert!(
+            polyline.len() <= 2 * MAX_POINTS_PER_SEGMENT,
+            "expected at most {} points, got {}",
+            2 * MAX_POINTS_PER_SEGMENT,
+            polyline.len()
+        );
+    }
+
+    #[test]
+    fn contains_distinguishes_fill_rules_on_overlapping_subpaths() {
+        // Two same-orientation squares overlapping in their top-right/
+        // bottom-left corners: the overlap is wound twice, so `NonZero`
+        // and `EvenOdd` must disagree there even though they agree
+        // everywhere else.
+        let mut bez = BezPath::new();
+        bez.move_to(Point::new(0.0, 0.0));
+        bez.line_to(Point::new(4.0, 0.0));
+        bez.line_to(Point::new(4.0, 4.0));
+        bez.line_to(Point::new(0.0, 4.0));
+        bez.close_path();
+        bez.move_to(Point::new(2.0, 2.0));
+        bez.line_to(Point::new(6.0, 2.0));
+        bez.line_to(Point::new(6.0, 6.0));
+        bez.line_to(Point::new(2.0, 6.0));
+        bez.close_path();
+
+        let overlap = Point::new(3.0, 3.0);
+        assert!(contains(&bez, overlap, FillRule::NonZero));
+        assert!(!contains(&bez, overlap, FillRule::EvenOdd));
+
+        let single_coverage = Point::new(1.0, 1.0);
+        assert!(contains(&bez, single_coverage, FillRule::NonZero));
+        assert!(contains(&bez, single_coverage, FillRule::EvenOdd));
+
+        let outside = Point::new(10.0, 10.0);
+        assert!(!contains(&bez, outside, FillRule::NonZero));
+        assert!(!contains(&bez, outside, FillRule::EvenOdd));
+    }
+
+    #[test]
+    fn contains_does_not_blow_up_on_a_pathological_huge_subpath() {
+        // `contains` flattens internally, so a malformed imported region
+        // with a huge/cusp-like subpath must stay bounded by
+        // MAX_POINTS_PER_SEGMENT rather than hanging the "select all
+        // marchers inside this shape" call. Regression test for the fix
+        // in `flatten_path`.
+        let mut bez = BezPath::new();
+        bez.move_to(Point::new(0.0, 0.0));
+        bez.curve_to(
+            Point::new(1e15, 1e15),
+            Point::new(-1e15, 1e15),
+            Point::new(0.0, 0.0),
+        );
+        bez.close_path();
+
+        assert!(!contains(&bez, Point::new(1e6, 1e6), FillRule::NonZero));
+    }
+
+    #[test]
+    fn bezpath_round_trips_through_svg_without_arcs() {
+        // Arcs are already lowered to cubics by the parser, so a path built
+        // only from M/L/Q/C/Z should parse -> serialize -> re-parse to an
+        // identical sequence of elements.
+        let original = "M10,20 L30,20 Q40,40 30,60 C20,80 0,70 10,50 Z";
+        let bez = svg_to_bezpath(original);
+        let serialized = bezpath_to_svg(&bez, 6);
+        let reparsed = svg_to_bezpath(&serialized);
+
+        let original_els: Vec<PathEl> = bez.elements().to_vec();
+        let reparsed_els: Vec<PathEl> = reparsed.elements().to_vec();
+        assert_eq!(original_els.len(), reparsed_els.len());
+        for (a, b) in original_els.iter().zip(reparsed_els.iter()) {
+            match (a, b) {
+                (PathEl::MoveTo(p1), PathEl::MoveTo(p2))
+                | (PathEl::LineTo(p1), PathEl::LineTo(p2)) => assert_points_close(*p1, *p2),
+                (PathEl::QuadTo(c1, p1), PathEl::QuadTo(c2, p2)) => {
+                    assert_points_close(*c1, *c2);
+                    assert_points_close(*p1, *p2);
+                }
+                (PathEl::CurveTo(c1a, c1b, p1), PathEl::CurveTo(c2a, c2b, p2)) => {
+                    assert_points_close(*c1a, *c2a);
+                    assert_points_close(*c1b, *c2b);
+                    assert_points_close(*p1, *p2);
+                }
+                (PathEl::ClosePath, PathEl::ClosePath) => {}
+                _ => panic!("element kind mismatch: {a:?} vs {b:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn bezpath_to_svg_trims_trailing_zeros() {
+        let mut bez = BezPath::new();
+        bez.move_to(Point::new(1.0, 2.5));
+        bez.line_to(Point::new(3.25, 4.0));
+
+        assert_eq!(bezpath_to_svg(&bez, 2), "M1,2.5 L3.25,4");
+    }
+
+    #[test]
+    fn format_num_drops_sign_on_negative_zero() {
+        assert_eq!(format_num(-0.0001, 2), "0");
+        assert_eq!(format_num(-0.0, 2), "0");
+        assert_eq!(format_num(-1.5, 2), "-1.5");
+    }
+
+    #[test]
+    fn zero_radius_arc_becomes_a_line() {
+        let path = svg_to_bezpath("M0,0 A0,5 0 0 1 10,0");
+        let els: Vec<PathEl> = path.elements().to_vec();
+        assert_eq!(
+            els,
+            vec![
+                PathEl::MoveTo(Point::new(0.0, 0.0)),
+                PathEl::LineTo(Point::new(10.0, 0.0)),
+            ]
+        );
+    }
 }